@@ -0,0 +1,194 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use tokio::{fs, io::AsyncReadExt};
+
+const READ_BUFFER_BYTES: usize = 256 * 1024;
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(
+        String::with_capacity(bytes.len() * 2),
+        |mut output, byte| {
+            let _ = write!(output, "{byte:02x}");
+            output
+        },
+    )
+}
+
+/// Digests a finished file can be checked against on demand.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha1,
+    Md5,
+}
+
+impl HashAlgorithm {
+    fn label(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "SHA-256",
+            HashAlgorithm::Sha1 => "SHA-1",
+            HashAlgorithm::Md5 => "MD5",
+        }
+    }
+
+    fn hex_len(self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 => 64,
+            HashAlgorithm::Sha1 => 40,
+            HashAlgorithm::Md5 => 32,
+        }
+    }
+}
+
+/// Normalizes a user-supplied SHA-256 digest to lowercase hex, rejecting malformed input.
+pub fn normalize_sha256(input: &str) -> Result<String, String> {
+    normalize_digest(HashAlgorithm::Sha256, input)
+}
+
+pub fn normalize_digest(algorithm: HashAlgorithm, input: &str) -> Result<String, String> {
+    let digest = input.trim().to_lowercase();
+    if digest.len() != algorithm.hex_len() || !digest.chars().all(|value| value.is_ascii_hexdigit())
+    {
+        return Err(format!("Invalid {} checksum", algorithm.label()));
+    }
+    Ok(digest)
+}
+
+pub async fn sha256_file(path: &Path) -> Result<String, String> {
+    digest_file::<Sha256>(path).await
+}
+
+/// A SHA-256 hasher already fed the first `len` bytes of `path`, to continue with the rest.
+pub async fn sha256_prefix(path: &Path, len: u64) -> Result<Sha256, String> {
+    let file = fs::File::open(path)
+        .await
+        .map_err(|error| format!("Unable to read file: {error}"))?;
+    let mut file = file.take(len);
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; READ_BUFFER_BYTES];
+    let mut hashed = 0;
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|error| format!("Unable to read file: {error}"))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        hashed += read as u64;
+    }
+    if hashed < len {
+        return Err("The partial file is shorter than expected".to_string());
+    }
+    Ok(hasher)
+}
+
+pub async fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<String, String> {
+    match algorithm {
+        HashAlgorithm::Sha256 => digest_file::<Sha256>(path).await,
+        HashAlgorithm::Sha1 => digest_file::<Sha1>(path).await,
+        HashAlgorithm::Md5 => digest_file::<Md5>(path).await,
+    }
+}
+
+async fn digest_file<D: Digest>(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path)
+        .await
+        .map_err(|error| format!("Unable to read file: {error}"))?;
+    let mut hasher = D::new();
+    let mut buffer = vec![0; READ_BUFFER_BYTES];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|error| format!("Unable to read file: {error}"))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Digests of files already hashed, reused while their size and modification time hold.
+#[derive(Default)]
+pub struct HashIndex {
+    entries: HashMap<PathBuf, IndexedFile>,
+}
+
+struct IndexedFile {
+    len: u64,
+    modified: Option<SystemTime>,
+    sha256: String,
+}
+
+impl HashIndex {
+    pub async fn record(&mut self, path: &Path, sha256: String) {
+        if let Ok(metadata) = fs::metadata(path).await {
+            self.entries.insert(
+                path.to_path_buf(),
+                IndexedFile {
+                    len: metadata.len(),
+                    modified: metadata.modified().ok(),
+                    sha256,
+                },
+            );
+        }
+    }
+
+    /// Finds a file directly inside `directory` with the given digest, hashing only files the
+    /// index doesn't know or that changed since they were hashed. Partial downloads are skipped.
+    pub async fn find_in_dir(&mut self, directory: &Path, sha256: &str) -> Option<PathBuf> {
+        let mut entries = fs::read_dir(directory).await.ok()?;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.ends_with(".part") || name.contains(".part.") {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let modified = metadata.modified().ok();
+            let cached = self
+                .entries
+                .get(&path)
+                .filter(|file| file.len == metadata.len() && file.modified == modified);
+            let digest = match cached {
+                Some(file) => file.sha256.clone(),
+                None => {
+                    let Ok(digest) = sha256_file(&path).await else {
+                        continue;
+                    };
+                    self.entries.insert(
+                        path.clone(),
+                        IndexedFile {
+                            len: metadata.len(),
+                            modified,
+                            sha256: digest.clone(),
+                        },
+                    );
+                    digest
+                }
+            };
+            if digest == sha256 {
+                return Some(path);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,336 @@
+use std::{net::SocketAddr, time::Duration};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+/// Multi-line replies longer than this are cut off; the text is only used in error messages.
+const MAX_REPLY_BYTES: usize = 64 * 1024;
+
+pub enum FtpError {
+    /// The connection failed, dropped, or went quiet.
+    Network(String),
+    /// A `4xx` reply: the same command may succeed later.
+    Transient(String),
+    /// A `5xx` reply, or one the client doesn't understand.
+    Rejected(String),
+}
+
+fn network(error: std::io::Error) -> FtpError {
+    FtpError::Network(format!("FTP connection error: {error}"))
+}
+
+pub struct Reply {
+    pub code: u16,
+    pub text: String,
+}
+
+impl Reply {
+    fn into_error(self) -> FtpError {
+        let message = format!("FTP server replied {} {}", self.code, self.text.trim());
+        match self.code {
+            400..=499 => FtpError::Transient(message),
+            _ => FtpError::Rejected(message),
+        }
+    }
+
+    fn expect(self, codes: &[u16]) -> Result<Reply, FtpError> {
+        if codes.contains(&self.code) {
+            Ok(self)
+        } else {
+            Err(self.into_error())
+        }
+    }
+
+    /// Parses the first line of a reply; the flag is set when more lines follow (`123-...`).
+    fn parse_first(line: &str) -> Result<(Reply, bool), FtpError> {
+        let code: u16 = line
+            .get(..3)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| FtpError::Rejected(format!("Malformed FTP reply: {line}")))?;
+        let text = line[3..].trim_start_matches([' ', '-']).to_string();
+        Ok((Reply { code, text }, line.as_bytes().get(3) == Some(&b'-')))
+    }
+
+    /// Adds a line of a multi-line reply and returns whether it was the last one.
+    fn push_line(&mut self, line: &str) -> bool {
+        if self.text.len() < MAX_REPLY_BYTES {
+            self.text.push('\n');
+            self.text.push_str(line);
+        }
+        line.starts_with(&format!("{} ", self.code))
+    }
+}
+
+/// The command as sent on the control connection. A line break in a path or password would
+/// start a second command, so those are refused.
+fn command_line(command: &str) -> Result<String, FtpError> {
+    if command.contains(['\r', '\n']) {
+        return Err(FtpError::Rejected("Invalid FTP argument".to_string()));
+    }
+    Ok(format!("{command}\r\n"))
+}
+
+/// The port from a `229 Entering Extended Passive Mode (|||port|)` reply.
+fn epsv_port(text: &str) -> Option<u16> {
+    text.split('|').nth(3).and_then(|port| port.parse().ok())
+}
+
+/// The port from a `227 Entering Passive Mode (h1,h2,h3,h4,p1,p2)` reply.
+fn pasv_port(text: &str) -> Option<u16> {
+    let numbers: Vec<u16> = text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect();
+    match numbers[..] {
+        [.., high, low] if numbers.len() >= 6 && high <= 255 && low <= 255 => {
+            Some(high * 256 + low)
+        }
+        _ => None,
+    }
+}
+
+/// Just enough of an FTP client to fetch one file in binary mode over a passive connection,
+/// optionally from an offset with `REST`.
+pub struct FtpClient {
+    control: BufReader<TcpStream>,
+    timeout: Duration,
+}
+
+impl FtpClient {
+    pub async fn connect(host: &str, port: u16, timeout: Duration) -> Result<Self, FtpError> {
+        let stream = tokio::time::timeout(timeout, TcpStream::connect((host, port)))
+            .await
+            .map_err(|_| FtpError::Network("FTP connection timed out".to_string()))?
+            .map_err(network)?;
+        let mut client = Self {
+            control: BufReader::new(stream),
+            timeout,
+        };
+        client.read_reply().await?.expect(&[220])?;
+        Ok(client)
+    }
+
+    pub async fn login(&mut self, user: &str, password: &str) -> Result<(), FtpError> {
+        let reply = self.command(&format!("USER {user}")).await?;
+        match reply.code {
+            230 => Ok(()),
+            331 | 332 => {
+                self.command(&format!("PASS {password}"))
+                    .await?
+                    .expect(&[202, 230])?;
+                Ok(())
+            }
+            _ => Err(reply.into_error()),
+        }
+    }
+
+    pub async fn binary(&mut self) -> Result<(), FtpError> {
+        self.command("TYPE I").await?.expect(&[200])?;
+        Ok(())
+    }
+
+    /// The file's size from `SIZE`, or `None` when the server doesn't implement it.
+    pub async fn size(&mut self, path: &str) -> Result<Option<u64>, FtpError> {
+        let reply = self.command(&format!("SIZE {path}")).await?;
+        Ok((reply.code == 213)
+            .then(|| reply.text.trim().parse().ok())
+            .flatten())
+    }
+
+    /// Whether the server accepts `REST`, checked with an offset of zero.
+    pub async fn supports_rest(&mut self) -> Result<bool, FtpError> {
+        Ok(self.command("REST 0").await?.code == 350)
+    }
+
+    /// Starts sending `path` from `offset` and returns the data connection. Call `finish`
+    /// once it has been read to the end.
+    pub async fn retrieve(&mut self, path: &str, offset: u64) -> Result<TcpStream, FtpError> {
+        let address = self.passive().await?;
+        let data = tokio::time::timeout(self.timeout, TcpStream::connect(address))
+            .await
+            .map_err(|_| FtpError::Network("FTP data connection timed out".to_string()))?
+            .map_err(network)?;
+        if offset > 0 {
+            self.command(&format!("REST {offset}"))
+                .await?
+                .expect(&[350])?;
+        }
+        self.command(&format!("RETR {path}"))
+            .await?
+            .expect(&[125, 150])?;
+        Ok(data)
+    }
+
+    /// Reads the reply that confirms the whole file was sent.
+    pub async fn finish(&mut self) -> Result<(), FtpError> {
+        self.read_reply().await?.expect(&[226, 250])?;
+        Ok(())
+    }
+
+    pub async fn quit(mut self) {
+        let _ = self.command("QUIT").await;
+    }
+
+    /// Asks for a passive data port with `EPSV`, falling back to `PASV`. The port is always
+    /// joined with the control connection's address, since servers behind NAT often
+    /// advertise a private one.
+    async fn passive(&mut self) -> Result<SocketAddr, FtpError> {
+        let peer = self.control.get_ref().peer_addr().map_err(network)?;
+        let reply = self.command("EPSV").await?;
+        if reply.code == 229 {
+            let port = epsv_port(&reply.text)
+                .ok_or_else(|| FtpError::Rejected("Malformed EPSV reply".to_string()))?;
+            return Ok(SocketAddr::new(peer.ip(), port));
+        }
+        let reply = self.command("PASV").await?.expect(&[227])?;
+        let port = pasv_port(&reply.text)
+            .ok_or_else(|| FtpError::Rejected("Malformed PASV reply".to_string()))?;
+        Ok(SocketAddr::new(peer.ip(), port))
+    }
+
+    async fn command(&mut self, command: &str) -> Result<Reply, FtpError> {
+        let line = command_line(command)?;
+        self.control
+            .get_mut()
+            .write_all(line.as_bytes())
+            .await
+            .map_err(network)?;
+        self.read_reply().await
+    }
+
+    async fn read_line(&mut self) -> Result<String, FtpError> {
+        let mut line = String::new();
+        let read = tokio::time::timeout(self.timeout, self.control.read_line(&mut line))
+            .await
+            .map_err(|_| FtpError::Network("FTP server stopped responding".to_string()))?
+            .map_err(network)?;
+        if read == 0 {
+            return Err(FtpError::Network(
+                "FTP server closed the connection".to_string(),
+            ));
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    /// Reads one reply, including every line of a multi-line (`123-...`) one.
+    async fn read_reply(&mut self) -> Result<Reply, FtpError> {
+        let (mut reply, mut more) = Reply::parse_first(&self.read_line().await?)?;
+        while more {
+            more = !reply.push_line(&self.read_line().await?);
+        }
+        Ok(reply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(lines: &[&str]) -> Result<Reply, FtpError> {
+        let (mut reply, mut more) = Reply::parse_first(lines[0])?;
+        for line in &lines[1..] {
+            assert!(more, "reply ended before {line:?}");
+            more = !reply.push_line(line);
+        }
+        assert!(!more, "reply still open");
+        Ok(reply)
+    }
+
+    #[test]
+    fn reads_single_line_replies() {
+        let Ok(reply) = read(&["213 1048576"]) else {
+            panic!("malformed")
+        };
+        assert_eq!((reply.code, reply.text.as_str()), (213, "1048576"));
+        let Ok(reply) = read(&["200"]) else {
+            panic!("malformed")
+        };
+        assert_eq!((reply.code, reply.text.as_str()), (200, ""));
+    }
+
+    #[test]
+    fn reads_multi_line_replies() {
+        let Ok(reply) = read(&[
+            "220-Welcome",
+            " 220 is not the end when indented",
+            "220-still going",
+            "220 Ready",
+        ]) else {
+            panic!("malformed")
+        };
+        assert_eq!(reply.code, 220);
+        assert_eq!(
+            reply.text,
+            "Welcome\n 220 is not the end when indented\n220-still going\n220 Ready"
+        );
+    }
+
+    #[test]
+    fn caps_long_multi_line_replies() {
+        let (mut reply, _) = Reply::parse_first("211-Features").ok().unwrap();
+        let line = "x".repeat(1024);
+        for _ in 0..200 {
+            assert!(!reply.push_line(&line));
+        }
+        assert!(reply.push_line("211 End"));
+        assert!(reply.text.len() < MAX_REPLY_BYTES + line.len() + 2);
+    }
+
+    #[test]
+    fn rejects_malformed_replies() {
+        for line in ["", "OK", "2x0 nope", "\u{e9}\u{e9}0"] {
+            assert!(
+                matches!(Reply::parse_first(line), Err(FtpError::Rejected(_))),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn maps_reply_codes_to_errors() {
+        let reply = |code| Reply {
+            code,
+            text: String::new(),
+        };
+        assert!(matches!(reply(421).into_error(), FtpError::Transient(_)));
+        assert!(matches!(reply(550).into_error(), FtpError::Rejected(_)));
+        assert!(reply(226).expect(&[226, 250]).is_ok());
+    }
+
+    #[test]
+    fn parses_epsv_ports() {
+        assert_eq!(
+            epsv_port("Entering Extended Passive Mode (|||6446|)"),
+            Some(6446)
+        );
+        assert_eq!(
+            epsv_port("Entering Extended Passive Mode (|||99999|)"),
+            None
+        );
+        assert_eq!(epsv_port("Entering Extended Passive Mode"), None);
+    }
+
+    #[test]
+    fn parses_pasv_ports() {
+        assert_eq!(
+            pasv_port("Entering Passive Mode (192,168,1,2,19,137)."),
+            Some(19 * 256 + 137)
+        );
+        assert_eq!(pasv_port("Entering Passive Mode 10,0,0,1,0,21"), Some(21));
+        assert_eq!(pasv_port("Entering Passive Mode (10,0,0,1,300,21)"), None);
+        assert_eq!(pasv_port("Entering Passive Mode (19,137)"), None);
+    }
+
+    #[test]
+    fn refuses_line_breaks_in_commands() {
+        assert!(matches!(
+            command_line("RETR a.bin").as_deref(),
+            Ok("RETR a.bin\r\n")
+        ));
+        for command in ["RETR a.bin\r\nDELE a.bin", "PASS x\ny", "USER a\r"] {
+            assert!(matches!(command_line(command), Err(FtpError::Rejected(_))));
+        }
+    }
+}
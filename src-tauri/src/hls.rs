@@ -0,0 +1,274 @@
+use url::Url;
+
+/// Playlists are plain text listing URLs; anything larger is not one worth parsing.
+pub const MAX_PLAYLIST_BYTES: usize = 4 * 1024 * 1024;
+
+/// One piece of a media playlist, optionally only a byte span (inclusive) of its URL.
+pub struct Segment {
+    pub url: Url,
+    pub range: Option<(u64, u64)>,
+}
+
+pub enum Playlist {
+    /// A master playlist, reduced to the rendition with the highest bandwidth.
+    Master(Url),
+    /// The segments of a finished (VOD) stream in playback order, an `EXT-X-MAP` init
+    /// section first when there is one.
+    Media(Vec<Segment>),
+}
+
+/// Parses an `.m3u8` playlist, resolving relative URIs against `base`. Encrypted and live
+/// streams are refused: the first can't be saved as playable files, and the second would
+/// stop at whatever the playlist listed at the time.
+pub fn parse(text: &str, base: &Url) -> Result<Playlist, String> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    if lines.next().map(|line| line.trim_start_matches('\u{feff}')) != Some("#EXTM3U") {
+        return Err("Not an HLS playlist".to_string());
+    }
+
+    let mut best: Option<(u64, Url)> = None;
+    let mut variant_bandwidth: Option<u64> = None;
+    let mut segments = Vec::new();
+    let mut pending_range: Option<(u64, Option<u64>)> = None;
+    // A byte range without an offset continues where the previous span of the same URL ended.
+    let mut previous_span: Option<(Url, u64)> = None;
+    let mut ended = false;
+    for line in lines {
+        if let Some(attributes) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            variant_bandwidth = Some(
+                attribute(attributes, "BANDWIDTH")
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(0),
+            );
+        } else if let Some(attributes) = line.strip_prefix("#EXT-X-KEY:") {
+            if attribute(attributes, "METHOD").is_some_and(|method| method != "NONE") {
+                return Err("Encrypted HLS streams are not supported.".to_string());
+            }
+        } else if let Some(attributes) = line.strip_prefix("#EXT-X-MAP:") {
+            let uri = attribute(attributes, "URI").ok_or("Malformed EXT-X-MAP tag")?;
+            let range = match attribute(attributes, "BYTERANGE") {
+                Some(range) => {
+                    let (length, offset) = parse_byte_range(range)?;
+                    Some(span(offset.unwrap_or(0), length)?)
+                }
+                None => None,
+            };
+            segments.push(Segment {
+                url: join(base, uri)?,
+                range,
+            });
+        } else if let Some(range) = line.strip_prefix("#EXT-X-BYTERANGE:") {
+            pending_range = Some(parse_byte_range(range)?);
+        } else if line == "#EXT-X-ENDLIST" {
+            ended = true;
+        } else if !line.starts_with('#') {
+            let url = join(base, line)?;
+            if let Some(bandwidth) = variant_bandwidth.take() {
+                if best.as_ref().is_none_or(|(best, _)| bandwidth > *best) {
+                    best = Some((bandwidth, url));
+                }
+                continue;
+            }
+            let range = match pending_range.take() {
+                Some((length, Some(offset))) => Some(span(offset, length)?),
+                Some((length, None)) => match &previous_span {
+                    Some((previous, end)) if *previous == url => Some(span(end + 1, length)?),
+                    _ => return Err("Malformed EXT-X-BYTERANGE tag".to_string()),
+                },
+                None => None,
+            };
+            previous_span = range.map(|(_, end)| (url.clone(), end));
+            segments.push(Segment { url, range });
+        }
+    }
+
+    if let Some((_, url)) = best {
+        return Ok(Playlist::Master(url));
+    }
+    if !ended {
+        return Err("Live HLS streams are not supported.".to_string());
+    }
+    if segments.is_empty() {
+        return Err("The playlist has no segments.".to_string());
+    }
+    Ok(Playlist::Media(segments))
+}
+
+fn join(base: &Url, uri: &str) -> Result<Url, String> {
+    let url = base
+        .join(uri)
+        .map_err(|_| format!("Invalid playlist entry: {uri}"))?;
+    match url.scheme() {
+        "http" | "https" => Ok(url),
+        _ => Err(format!("Unsupported playlist entry: {uri}")),
+    }
+}
+
+/// `<length>[@<offset>]`, as used by `EXT-X-BYTERANGE` and the `BYTERANGE` attribute.
+fn parse_byte_range(value: &str) -> Result<(u64, Option<u64>), String> {
+    let malformed = || format!("Malformed byte range: {value}");
+    let (length, offset) = match value.split_once('@') {
+        Some((length, offset)) => (length, Some(offset)),
+        None => (value, None),
+    };
+    let length = length.trim().parse().map_err(|_| malformed())?;
+    let offset = offset
+        .map(|offset| offset.trim().parse().map_err(|_| malformed()))
+        .transpose()?;
+    Ok((length, offset))
+}
+
+fn span(start: u64, length: u64) -> Result<(u64, u64), String> {
+    match length {
+        0 => Err("Empty byte range in playlist".to_string()),
+        _ => Ok((start, start + length - 1)),
+    }
+}
+
+/// Looks up `name` in an attribute list such as `BANDWIDTH=1280000,CODECS="avc1,mp4a"`.
+fn attribute<'a>(list: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = list;
+    while !rest.is_empty() {
+        let (key, after) = rest.split_once('=')?;
+        let (value, next) = match after.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"')?;
+                (&quoted[..end], &quoted[end + 1..])
+            }
+            None => after
+                .split_once(',')
+                .map_or((after, ""), |(value, next)| (value, next)),
+        };
+        if key.trim() == name {
+            return Some(value);
+        }
+        rest = next.trim_start_matches(',');
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://cdn.example.com/show/master.m3u8").unwrap()
+    }
+
+    fn media(text: &str) -> Vec<Segment> {
+        match parse(text, &base()).unwrap() {
+            Playlist::Media(segments) => segments,
+            Playlist::Master(_) => panic!("expected a media playlist"),
+        }
+    }
+
+    #[test]
+    fn picks_the_highest_bandwidth_variant() {
+        let text = "#EXTM3U\n\
+            #EXT-X-STREAM-INF:BANDWIDTH=800000,CODECS=\"avc1.4d401f,mp4a.40.2\"\n\
+            low/index.m3u8\n\
+            #EXT-X-STREAM-INF:RESOLUTION=1920x1080,BANDWIDTH=5000000\n\
+            high/index.m3u8\n\
+            #EXT-X-STREAM-INF:BANDWIDTH=2000000\n\
+            https://other.example.com/mid.m3u8\n";
+        match parse(text, &base()).unwrap() {
+            Playlist::Master(url) => {
+                assert_eq!(url.as_str(), "https://cdn.example.com/show/high/index.m3u8")
+            }
+            Playlist::Media(_) => panic!("expected a master playlist"),
+        }
+    }
+
+    #[test]
+    fn resolves_segments_in_order() {
+        let segments = media(
+            "\u{feff}#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:4,\nseg0.ts\n\n\
+             #EXTINF:4,\n/abs/seg1.ts\n#EXT-X-ENDLIST\n",
+        );
+        let urls: Vec<_> = segments
+            .iter()
+            .map(|segment| segment.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "https://cdn.example.com/show/seg0.ts",
+                "https://cdn.example.com/abs/seg1.ts",
+            ]
+        );
+        assert!(segments.iter().all(|segment| segment.range.is_none()));
+    }
+
+    #[test]
+    fn continues_byte_ranges_without_an_offset() {
+        let segments = media(
+            "#EXTM3U\n#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"720@16\"\n\
+             #EXT-X-BYTERANGE:1000@0\npacked.ts\n#EXT-X-BYTERANGE:500\npacked.ts\n\
+             #EXT-X-BYTERANGE:200\npacked.ts\n#EXT-X-ENDLIST\n",
+        );
+        let ranges: Vec<_> = segments.iter().map(|segment| segment.range).collect();
+        assert_eq!(
+            ranges,
+            [
+                Some((16, 735)),
+                Some((0, 999)),
+                Some((1000, 1499)),
+                Some((1500, 1699)),
+            ]
+        );
+        assert_eq!(
+            segments[0].url.as_str(),
+            "https://cdn.example.com/show/init.mp4"
+        );
+    }
+
+    #[test]
+    fn rejects_byte_ranges_that_cannot_continue() {
+        for text in [
+            "#EXTM3U\n#EXT-X-BYTERANGE:500\na.ts\n#EXT-X-ENDLIST\n",
+            "#EXTM3U\n#EXT-X-BYTERANGE:500@0\na.ts\n#EXT-X-BYTERANGE:500\nb.ts\n#EXT-X-ENDLIST\n",
+            "#EXTM3U\n#EXT-X-BYTERANGE:0@0\na.ts\n#EXT-X-ENDLIST\n",
+            "#EXTM3U\n#EXT-X-BYTERANGE:abc\na.ts\n#EXT-X-ENDLIST\n",
+        ] {
+            assert!(parse(text, &base()).is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn refuses_encrypted_and_live_streams() {
+        let encrypted = "#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\"\n#EXTINF:4,\n\
+                         seg0.ts\n#EXT-X-ENDLIST\n";
+        let error = parse(encrypted, &base()).err().unwrap();
+        assert!(error.contains("Encrypted"), "{error}");
+        let live = "#EXTM3U\n#EXTINF:4,\nseg0.ts\n";
+        let error = parse(live, &base()).err().unwrap();
+        assert!(error.contains("Live"), "{error}");
+        let clear = "#EXTM3U\n#EXT-X-KEY:METHOD=NONE\n#EXTINF:4,\nseg0.ts\n#EXT-X-ENDLIST\n";
+        assert_eq!(media(clear).len(), 1);
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(parse("<html></html>", &base()).is_err());
+        assert!(parse("#EXTM3U\n#EXT-X-ENDLIST\n", &base()).is_err());
+        assert!(parse("#EXTM3U\nftp://host/seg.ts\n#EXT-X-ENDLIST\n", &base()).is_err());
+    }
+
+    #[test]
+    fn reads_quoted_attributes() {
+        let list = "CODECS=\"avc1,mp4a\",BANDWIDTH=1280000,URI=\"a=b,c\"";
+        assert_eq!(attribute(list, "CODECS"), Some("avc1,mp4a"));
+        assert_eq!(attribute(list, "BANDWIDTH"), Some("1280000"));
+        assert_eq!(attribute(list, "URI"), Some("a=b,c"));
+        assert_eq!(attribute(list, "METHOD"), None);
+        assert_eq!(attribute("URI=\"unterminated", "URI"), None);
+    }
+
+    #[test]
+    fn parses_byte_range_values() {
+        assert_eq!(parse_byte_range("100@20"), Ok((100, Some(20))));
+        assert_eq!(parse_byte_range(" 100 "), Ok((100, None)));
+        assert!(parse_byte_range("@20").is_err());
+        assert!(parse_byte_range("100@").is_err());
+    }
+}
@@ -1,20 +1,104 @@
+mod checksum;
+mod content_encoding;
 mod downloads;
+mod ftp;
+mod hls;
+mod rate_limit;
+mod torrent;
+
+use tauri::Manager;
+
+/// For Rust-side consumers that follow downloads with `DownloadManager::subscribe_status` or
+/// embed the manager around their own client with `DownloadManager::with_client`.
+pub use downloads::{DownloadManager, DownloadStatus};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .manage(downloads::DownloadManager::new())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(|app| {
+            let state_path = app.path().app_data_dir()?.join("downloads.json");
+            let manager = downloads::DownloadManager::new(state_path);
+            manager.recover_partials(app.handle());
+            manager.restore_schedules(app.handle());
+            app.manage(manager);
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             downloads::list_downloads,
+            downloads::list_downloads_by_status,
+            downloads::list_downloads_by_tag,
+            downloads::get_download,
+            downloads::wait_for_download,
+            downloads::get_stats,
+            downloads::get_speed_history,
             downloads::set_speed_limits,
+            downloads::set_schedule,
+            downloads::set_download_speed_limit,
+            downloads::get_concurrency,
+            downloads::set_max_concurrent,
+            downloads::set_per_host_limit,
+            downloads::set_max_segment_connections,
+            downloads::set_max_retries,
+            downloads::set_max_redirects,
+            downloads::set_timeouts,
+            downloads::set_proxy,
+            downloads::set_no_proxy,
+            downloads::set_user_agent,
+            downloads::set_cookies,
+            downloads::set_disk_space_check,
+            downloads::set_auto_resume,
+            downloads::set_categories,
+            downloads::set_ipfs_gateway,
+            downloads::set_default_directory,
+            downloads::set_on_complete_command,
+            downloads::set_notifications,
+            downloads::set_max_size,
+            downloads::set_write_buffer_size,
+            downloads::set_buffered_chunks,
+            downloads::set_speed_smoothing,
+            downloads::set_dedupe_by_checksum,
+            downloads::set_decompression,
+            downloads::set_restart_ignored_range,
+            downloads::set_debug_logging,
+            downloads::set_progress_mode,
+            downloads::set_temp_directory,
+            downloads::probe_url,
+            downloads::measure_bandwidth,
+            downloads::cancel_bandwidth_measurement,
+            downloads::parse_urls,
             downloads::start_download,
+            downloads::validate_download,
+            downloads::start_downloads,
             downloads::pause_download,
             downloads::resume_download,
+            downloads::pause_all,
+            downloads::resume_all,
             downloads::cancel_download,
             downloads::restart_download,
+            downloads::restart_all_failed,
+            downloads::duplicate_download,
             downloads::remove_download,
+            downloads::list_trash,
+            downloads::restore_download,
+            downloads::empty_trash,
+            downloads::set_priority,
+            downloads::set_tags,
+            downloads::rename_download,
+            downloads::move_download,
+            downloads::reveal_download,
+            downloads::verify_download,
+            downloads::export_as_curl,
+            downloads::update_external_progress,
+            downloads::clear_completed,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                let manager = app.state::<downloads::DownloadManager>();
+                tauri::async_runtime::block_on(manager.shutdown());
+            }
+        });
 }
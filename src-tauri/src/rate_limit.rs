@@ -0,0 +1,134 @@
+use std::time::{Duration, Instant};
+
+/// Fraction of a second of traffic the bucket may accumulate while idle.
+const BURST_SECONDS: f64 = 0.25;
+
+/// A token bucket measured in bytes. Consuming more tokens than are available puts the bucket
+/// into debt, and the caller waits until refill pays it back, so large chunks are paced exactly.
+pub struct TokenBucket {
+    rate: u64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new() -> Self {
+        Self {
+            rate: 0,
+            capacity: 0.0,
+            tokens: 0.0,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn set_rate(&mut self, rate: u64, now: Instant) {
+        self.refill(now);
+        self.rate = rate;
+        self.capacity = (rate as f64 * BURST_SECONDS).max(1.0);
+        self.tokens = self.tokens.min(self.capacity);
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.rate as f64).min(self.capacity);
+    }
+
+    /// Takes `amount` tokens at `rate` bytes per second and returns how long the caller must
+    /// wait before using them. A `rate` of zero means unlimited.
+    pub fn reserve(&mut self, rate: u64, amount: u64) -> Duration {
+        self.reserve_at(rate, amount, Instant::now())
+    }
+
+    fn reserve_at(&mut self, rate: u64, amount: u64, now: Instant) -> Duration {
+        if rate == 0 {
+            // Nothing refills while unlimited, so debt from before would outlive the pause.
+            self.rate = 0;
+            self.tokens = 0.0;
+            return Duration::ZERO;
+        }
+        if rate != self.rate {
+            self.set_rate(rate, now);
+        } else {
+            self.refill(now);
+        }
+        self.tokens -= amount as f64;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / rate as f64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_wait(wait: Duration, expected_secs: f64) {
+        let secs = wait.as_secs_f64();
+        assert!(
+            (secs - expected_secs).abs() < 1e-6,
+            "waited {secs}s, expected {expected_secs}s"
+        );
+    }
+
+    #[test]
+    fn paces_a_steady_stream_at_the_rate() {
+        let mut bucket = TokenBucket::new();
+        let start = bucket.last_refill;
+        let mut now = start;
+        let mut sent = 0;
+        for chunk in [100, 4096, 1, 16384, 700].into_iter().cycle().take(200) {
+            now += bucket.reserve_at(1000, chunk, now);
+            sent += chunk;
+        }
+        // Each wait pays for its own chunk, so the average lands on the rate.
+        let rate = sent as f64 / now.duration_since(start).as_secs_f64();
+        assert!((rate - 1000.0).abs() / 1000.0 < 0.01, "{rate} B/s");
+    }
+
+    #[test]
+    fn waits_for_each_chunk_in_turn() {
+        let mut bucket = TokenBucket::new();
+        let start = bucket.last_refill;
+        assert_wait(bucket.reserve_at(1000, 100, start), 0.1);
+        assert_wait(bucket.reserve_at(1000, 100, start), 0.2);
+        let later = start + Duration::from_millis(200);
+        assert_wait(bucket.reserve_at(1000, 100, later), 0.1);
+    }
+
+    #[test]
+    fn allows_a_quarter_second_burst_after_idling() {
+        let mut bucket = TokenBucket::new();
+        let start = bucket.last_refill;
+        bucket.reserve_at(1000, 1, start);
+        let idle = start + Duration::from_secs(10);
+        assert_wait(bucket.reserve_at(1000, 1, idle), 0.0);
+        assert_wait(bucket.reserve_at(1000, 249, idle), 0.0);
+        assert_wait(bucket.reserve_at(1000, 100, idle), 0.1);
+    }
+
+    #[test]
+    fn applies_a_rate_change_to_the_next_chunk() {
+        let mut bucket = TokenBucket::new();
+        let start = bucket.last_refill;
+        assert_wait(bucket.reserve_at(1000, 100, start), 0.1);
+        // The debt carries over and is paid back at the new rate.
+        assert_wait(bucket.reserve_at(500, 100, start), 0.4);
+        let paid = start + Duration::from_millis(400);
+        assert_wait(bucket.reserve_at(2000, 1000, paid), 0.5);
+    }
+
+    #[test]
+    fn forgets_debt_while_unlimited() {
+        let mut bucket = TokenBucket::new();
+        let start = bucket.last_refill;
+        assert_wait(bucket.reserve_at(1000, 1000, start), 1.0);
+        assert_wait(bucket.reserve_at(0, 1_000_000, start), 0.0);
+        assert_wait(bucket.reserve_at(1000, 100, start), 0.1);
+    }
+}
@@ -0,0 +1,363 @@
+use std::path::Path;
+
+use futures::StreamExt;
+use tokio::fs;
+use url::Url;
+
+/// Torrent files are small; anything larger is almost certainly not one.
+const MAX_TORRENT_BYTES: usize = 10 * 1024 * 1024;
+const MAX_DEPTH: usize = 64;
+
+/// The parts of a `.torrent` file shown before any pieces are fetched.
+pub struct TorrentMetadata {
+    pub name: String,
+    pub total_bytes: u64,
+}
+
+enum Value<'a> {
+    Int(i64),
+    Bytes(&'a [u8]),
+    List(Vec<Value<'a>>),
+    Dict(Vec<(&'a [u8], Value<'a>)>),
+}
+
+impl<'a> Value<'a> {
+    fn get(&self, key: &str) -> Option<&Value<'a>> {
+        match self {
+            Value::Dict(entries) => entries
+                .iter()
+                .find(|(name, _)| *name == key.as_bytes())
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<String> {
+        match self {
+            Value::Bytes(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+            _ => None,
+        }
+    }
+}
+
+struct Decoder<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn read_until(&mut self, end: u8) -> Result<&'a str, String> {
+        let start = self.position;
+        let length = self.input[start..]
+            .iter()
+            .position(|byte| *byte == end)
+            .ok_or_else(|| "Truncated torrent file".to_string())?;
+        self.position = start + length + 1;
+        std::str::from_utf8(&self.input[start..start + length])
+            .map_err(|_| "Malformed torrent file".to_string())
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value<'a>, String> {
+        if depth > MAX_DEPTH {
+            return Err("Torrent file is nested too deeply".to_string());
+        }
+        match self.peek() {
+            Some(b'i') => {
+                self.position += 1;
+                self.read_until(b'e')?
+                    .parse()
+                    .map(Value::Int)
+                    .map_err(|_| "Malformed torrent file".to_string())
+            }
+            Some(b'l') => {
+                self.position += 1;
+                let mut items = Vec::new();
+                while self.peek() != Some(b'e') {
+                    items.push(self.value(depth + 1)?);
+                }
+                self.position += 1;
+                Ok(Value::List(items))
+            }
+            Some(b'd') => {
+                self.position += 1;
+                let mut entries = Vec::new();
+                while self.peek() != Some(b'e') {
+                    let key = self.bytes()?;
+                    entries.push((key, self.value(depth + 1)?));
+                }
+                self.position += 1;
+                Ok(Value::Dict(entries))
+            }
+            Some(b'0'..=b'9') => self.bytes().map(Value::Bytes),
+            _ => Err("Malformed torrent file".to_string()),
+        }
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], String> {
+        let length: usize = self
+            .read_until(b':')?
+            .parse()
+            .map_err(|_| "Malformed torrent file".to_string())?;
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.input.len())
+            .ok_or_else(|| "Truncated torrent file".to_string())?;
+        let bytes = &self.input[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+}
+
+/// Reads the display name and total size from bencoded torrent metainfo. Multi-file torrents
+/// report the sum of their file lengths.
+pub fn parse_metainfo(bytes: &[u8]) -> Result<TorrentMetadata, String> {
+    let root = Decoder {
+        input: bytes,
+        position: 0,
+    }
+    .value(0)?;
+    let info = root
+        .get("info")
+        .ok_or_else(|| "Torrent file has no info dictionary".to_string())?;
+    let name = info
+        .get("name.utf-8")
+        .or_else(|| info.get("name"))
+        .and_then(Value::as_str)
+        .filter(|name| !name.trim().is_empty())
+        .ok_or_else(|| "Torrent file has no name".to_string())?;
+
+    let total_bytes = match (info.get("length"), info.get("files")) {
+        (Some(length), _) => length.as_int(),
+        // Lengths come from the file, so a negative or overflowing one rejects it.
+        (None, Some(Value::List(files))) => files.iter().try_fold(0i64, |total, file| {
+            let length = file.get("length").and_then(Value::as_int)?;
+            total.checked_add(length).filter(|_| length >= 0)
+        }),
+        _ => None,
+    }
+    .filter(|total| *total >= 0)
+    .ok_or_else(|| "Torrent file has no length".to_string())?;
+
+    Ok(TorrentMetadata {
+        name,
+        total_bytes: total_bytes as u64,
+    })
+}
+
+/// What a magnet link says about its torrent before any peer is contacted.
+pub struct MagnetLink {
+    pub display_name: Option<String>,
+    /// Lowercased hash from the `urn:btih:` exact topic.
+    pub info_hash: Option<String>,
+    pub tracker_count: usize,
+}
+
+impl MagnetLink {
+    /// The `dn` name, or a short label from the info hash when the link has none.
+    pub fn label(&self) -> Option<String> {
+        self.display_name.clone().or_else(|| {
+            self.info_hash
+                .as_ref()
+                .map(|hash| format!("magnet-{}", &hash[..hash.len().min(8)]))
+        })
+    }
+}
+
+pub fn parse_magnet(link: &str) -> Result<MagnetLink, String> {
+    let url = Url::parse(link.trim()).map_err(|_| "Invalid magnet link".to_string())?;
+    if url.scheme() != "magnet" {
+        return Err("Invalid magnet link".to_string());
+    }
+    let mut magnet = MagnetLink {
+        display_name: None,
+        info_hash: None,
+        tracker_count: 0,
+    };
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "dn" if !value.trim().is_empty() => magnet.display_name = Some(value.into_owned()),
+            "xt" => {
+                if let Some(hash) = value.strip_prefix("urn:btih:") {
+                    magnet.info_hash = Some(hash.to_ascii_lowercase());
+                }
+            }
+            "tr" => magnet.tracker_count += 1,
+            _ => {}
+        }
+    }
+    Ok(magnet)
+}
+
+/// Loads a `.torrent` from an http(s) URL, a `file://` URL, or a local path.
+pub async fn read_source(client: &reqwest::Client, source: &str) -> Result<Vec<u8>, String> {
+    let parsed = Url::parse(source).ok();
+    match parsed.as_ref().map(Url::scheme) {
+        Some("http" | "https") => {
+            let response = client
+                .get(source)
+                .send()
+                .await
+                .map_err(|error| format!("Failed to fetch torrent: {error}"))?;
+            if !response.status().is_success() {
+                return Err(format!("Failed to fetch torrent: {}", response.status()));
+            }
+            let mut bytes = Vec::new();
+            let mut stream = response.bytes_stream();
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.map_err(|error| format!("Failed to fetch torrent: {error}"))?;
+                bytes.extend_from_slice(&chunk);
+                if bytes.len() > MAX_TORRENT_BYTES {
+                    return Err("Torrent file is too large".to_string());
+                }
+            }
+            Ok(bytes)
+        }
+        Some("file") => {
+            let path = parsed
+                .and_then(|url| url.to_file_path().ok())
+                .ok_or_else(|| "Invalid torrent path".to_string())?;
+            read_file(&path).await
+        }
+        _ => read_file(Path::new(source)).await,
+    }
+}
+
+async fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    let size = fs::metadata(path)
+        .await
+        .map_err(|error| format!("Failed to read torrent: {error}"))?
+        .len();
+    if size > MAX_TORRENT_BYTES as u64 {
+        return Err("Torrent file is too large".to_string());
+    }
+    fs::read(path)
+        .await
+        .map_err(|error| format!("Failed to read torrent: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metainfo(info: &str) -> Result<TorrentMetadata, String> {
+        parse_metainfo(format!("d8:announce9:http://t/4:infod{info}ee").as_bytes())
+    }
+
+    #[test]
+    fn reads_single_file_torrents() {
+        let torrent = metainfo("6:lengthi1048576e4:name9:movie.mkv").unwrap();
+        assert_eq!(torrent.name, "movie.mkv");
+        assert_eq!(torrent.total_bytes, 1_048_576);
+    }
+
+    #[test]
+    fn sums_multi_file_torrents() {
+        let torrent = metainfo("5:filesld6:lengthi10eed6:lengthi32eee4:name6:folder").unwrap();
+        assert_eq!(torrent.name, "folder");
+        assert_eq!(torrent.total_bytes, 42);
+    }
+
+    #[test]
+    fn prefers_the_utf8_name() {
+        let torrent = metainfo("6:lengthi1e4:name3:old10:name.utf-85:caf\u{e9}").unwrap();
+        assert_eq!(torrent.name, "caf\u{e9}");
+    }
+
+    #[test]
+    fn rejects_bad_lengths() {
+        assert!(metainfo("6:lengthi-1e4:name1:x").is_err());
+        assert!(metainfo("5:filesld6:lengthi-5eed6:lengthi10eee4:name1:x").is_err());
+        assert!(
+            metainfo("5:filesld6:lengthi9223372036854775807eed6:lengthi1eee4:name1:x").is_err()
+        );
+        assert!(metainfo("5:filesld4:pathl1:aeee4:name1:x").is_err());
+        assert!(metainfo("4:name1:x").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_names() {
+        assert!(metainfo("6:lengthi1e").is_err());
+        assert!(metainfo("6:lengthi1e4:name2:  ").is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_and_garbage_input() {
+        for input in [
+            &b""[..],
+            b"not a torrent",
+            b"d4:infod4:name",
+            b"d4:infod4:name5:x",
+            b"d4:infod6:lengthi12",
+            b"d4:info",
+            b"d99999999999999999999999:ae",
+            b"d18446744073709551615:a",
+            b"i12x3e",
+        ] {
+            assert!(parse_metainfo(input).is_err(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn limits_nesting_depth() {
+        let nested = format!("{}{}", "l".repeat(MAX_DEPTH + 2), "e".repeat(MAX_DEPTH + 2));
+        let error = parse_metainfo(nested.as_bytes()).err().unwrap();
+        assert!(error.contains("nested too deeply"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn reads_local_sources() {
+        let path = std::env::temp_dir().join(format!("fdm-test-{}.torrent", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"d4:infodee").unwrap();
+        let client = reqwest::Client::new();
+        let source = path.display().to_string();
+        assert_eq!(read_source(&client, &source).await.unwrap(), b"d4:infodee");
+        let _ = std::fs::remove_file(&path);
+        assert!(read_source(&client, &source).await.is_err());
+    }
+
+    #[test]
+    fn decodes_magnet_names_and_counts_trackers() {
+        let magnet = parse_magnet(
+            "magnet:?xt=urn:btih:ABCDEF0123456789ABCDEF0123456789ABCDEF01&dn=My%20Show+S01\
+             &tr=udp%3A%2F%2Fa&tr=udp%3A%2F%2Fb&tr=udp%3A%2F%2Fa",
+        )
+        .unwrap();
+        assert_eq!(magnet.display_name.as_deref(), Some("My Show S01"));
+        assert_eq!(magnet.tracker_count, 3);
+        assert_eq!(
+            magnet.info_hash.as_deref(),
+            Some("abcdef0123456789abcdef0123456789abcdef01")
+        );
+    }
+
+    #[test]
+    fn accepts_base32_info_hashes() {
+        let magnet = parse_magnet("magnet:?xt=urn:btih:MFRGGZDFMZTWQ2LKNNWG23TPOBYXE43U").unwrap();
+        assert_eq!(
+            magnet.info_hash.as_deref(),
+            Some("mfrggzdfmztwq2lknnwg23tpobyxe43u")
+        );
+        assert_eq!(magnet.label().as_deref(), Some("magnet-mfrggzdf"));
+    }
+
+    #[test]
+    fn handles_magnets_without_a_hash() {
+        let magnet = parse_magnet("magnet:?dn=only-a-name&xt=urn:sha1:abc").unwrap();
+        assert!(magnet.info_hash.is_none());
+        assert_eq!(magnet.label().as_deref(), Some("only-a-name"));
+        assert!(parse_magnet("magnet:?dn=%20").unwrap().label().is_none());
+        assert!(parse_magnet("https://example.com/?xt=urn:btih:abc").is_err());
+    }
+}
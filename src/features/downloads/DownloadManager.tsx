@@ -28,6 +28,7 @@ import {
   removeDownload,
   restartDownload,
   resumeDownload,
+  revealDownload,
   setSpeedLimits,
   startDownload,
 } from "@/features/downloads/api"
@@ -48,8 +49,11 @@ const defaultUnits = ["KB/s", "MB/s", "GB/s"]
 
 const statusTone: Record<DownloadStatus, "default" | "secondary" | "destructive"> = {
   queued: "secondary",
+  scheduled: "secondary",
   running: "default",
+  retrying: "secondary",
   paused: "secondary",
+  verifying: "secondary",
   completed: "default",
   failed: "destructive",
   canceled: "secondary",
@@ -58,8 +62,11 @@ const statusTone: Record<DownloadStatus, "default" | "secondary" | "destructive"
 
 const statusLabel: Record<DownloadStatus, string> = {
   queued: "Queued",
+  scheduled: "Scheduled",
   running: "Running",
+  retrying: "Retrying",
   paused: "Paused",
+  verifying: "Verifying",
   completed: "Completed",
   failed: "Failed",
   canceled: "Canceled",
@@ -74,7 +81,12 @@ const filterOptions = [
 ]
 
 const isActiveStatus = (status: DownloadStatus) =>
-  status === "running" || status === "queued" || status === "paused"
+  status === "running" ||
+  status === "retrying" ||
+  status === "queued" ||
+  status === "scheduled" ||
+  status === "paused" ||
+  status === "verifying"
 
 export function DownloadManager() {
   const [downloads, setDownloads] = useState<DownloadInfo[]>([])
@@ -197,6 +209,14 @@ export function DownloadManager() {
     }
   }
 
+  const handleReveal = async (download: DownloadInfo) => {
+    try {
+      await revealDownload(download.id)
+    } catch (error) {
+      setErrorMessage(error instanceof Error ? error.message : "Unable to show in folder")
+    }
+  }
+
   const handleRemove = async (download: DownloadInfo) => {
     try {
       await removeDownload(download.id)
@@ -406,7 +426,7 @@ export function DownloadManager() {
                 )}
                 {visibleDownloads.map((download) => {
                   const percent = formatPercent(download.downloadedBytes, download.totalBytes)
-                  const isRunning = download.status === "running"
+                  const isRunning = download.status === "running" || download.status === "retrying"
                   const canResume =
                     download.status === "paused" ||
                     (download.status === "failed" && download.resumeSupported)
@@ -424,6 +444,11 @@ export function DownloadManager() {
                             {download.kind !== "http" && (
                               <Badge variant="secondary">{download.kind.toUpperCase()}</Badge>
                             )}
+                            {download.tags?.map((tag) => (
+                              <Badge key={tag} variant="outline">
+                                {tag}
+                              </Badge>
+                            ))}
                           </div>
                           <p className="text-muted-foreground text-xs break-all">{download.url}</p>
                         </div>
@@ -468,6 +493,15 @@ export function DownloadManager() {
                               Open
                             </Button>
                           )}
+                          {download.status === "completed" && (
+                            <Button
+                              size="sm"
+                              variant="outline"
+                              onClick={() => handleReveal(download)}
+                            >
+                              Show in Folder
+                            </Button>
+                          )}
                           {download.status === "external" && (
                             <Button size="sm" onClick={() => handleOpen(download.url)}>
                               Open External
@@ -514,6 +548,23 @@ export function DownloadManager() {
                       {download.error && (
                         <div className="text-destructive text-xs">{download.error}</div>
                       )}
+                      {download.notModified && (
+                        <div className="text-muted-foreground text-xs">
+                          Unchanged on the server; nothing downloaded
+                        </div>
+                      )}
+                      {(download.retryCount ?? 0) > 0 && (
+                        <div className="text-muted-foreground text-xs">
+                          Reconnected {download.retryCount} times
+                        </div>
+                      )}
+                      {download.copies
+                        ?.filter((copy) => !copy.success)
+                        .map((copy) => (
+                          <div key={copy.path} className="text-destructive text-xs">
+                            {copy.path}: {copy.error}
+                          </div>
+                        ))}
                     </div>
                   )
                 })}
@@ -1,24 +1,240 @@
 import { invoke } from "@tauri-apps/api/core"
-import type { DownloadInfo, SpeedLimits, DownloadKind } from "@/features/downloads/types"
+import { listen } from "@tauri-apps/api/event"
+import type {
+  BandwidthMeasurement,
+  BatchStartResult,
+  ConcurrencyInfo,
+  DetectedUrl,
+  DownloadFailure,
+  DownloadInfo,
+  DownloadMetadata,
+  DownloadSettings,
+  DownloadSort,
+  DownloadStats,
+  DownloadStatus,
+  HashAlgorithm,
+  HookResult,
+  ProgressMode,
+  ProgressSummary,
+  RequestLog,
+  ScheduleRule,
+  SpeedLimits,
+  SpeedSample,
+  StartDownloadPayload,
+  UrlProbe,
+  ValidatedPayload,
+  VerifyResult,
+} from "@/features/downloads/types"
 
-export const listDownloads = () => invoke<DownloadInfo[]>("list_downloads")
+export const listDownloads = (sortBy?: DownloadSort, ascending?: boolean) =>
+  invoke<DownloadInfo[]>("list_downloads", { sortBy, ascending })
 
-export const startDownload = (payload: {
-  url: string
-  fileName?: string
-  directory?: string
-  kind?: DownloadKind
-}) => invoke<DownloadInfo>("start_download", { payload })
+export const listDownloadsByStatus = (statuses: DownloadStatus[]) =>
+  invoke<DownloadInfo[]>("list_downloads_by_status", { statuses })
+
+export const listDownloadsByTag = (tag: string) =>
+  invoke<DownloadInfo[]>("list_downloads_by_tag", { tag })
+
+export const getDownload = (id: string) => invoke<DownloadInfo>("get_download", { id })
+
+export const waitForDownload = (id: string, timeoutMs?: number) =>
+  invoke<DownloadInfo>("wait_for_download", { id, timeoutMs })
+
+export const getStats = () => invoke<DownloadStats>("get_stats")
+
+export const getSpeedHistory = () => invoke<SpeedSample[]>("get_speed_history")
+
+export const probeUrl = (url: string) => invoke<UrlProbe>("probe_url", { url })
+
+export const measureBandwidth = (url: string, durationMs: number) =>
+  invoke<BandwidthMeasurement>("measure_bandwidth", { url, durationMs })
+
+export const cancelBandwidthMeasurement = () => invoke<void>("cancel_bandwidth_measurement")
+
+export const parseUrls = (text: string) => invoke<DetectedUrl[]>("parse_urls", { text })
+
+export const startDownload = (payload: StartDownloadPayload) =>
+  invoke<DownloadInfo>("start_download", { payload })
+
+export const validateDownload = (payload: StartDownloadPayload) =>
+  invoke<ValidatedPayload>("validate_download", { payload })
+
+export const startDownloads = (payloads: StartDownloadPayload[]) =>
+  invoke<BatchStartResult>("start_downloads", { payloads })
 
 export const pauseDownload = (id: string) => invoke<DownloadInfo>("pause_download", { id })
 
 export const resumeDownload = (id: string) => invoke<DownloadInfo>("resume_download", { id })
 
-export const cancelDownload = (id: string) => invoke<DownloadInfo>("cancel_download", { id })
+export const pauseAll = () => invoke<DownloadInfo[]>("pause_all")
+
+export const resumeAll = () => invoke<DownloadInfo[]>("resume_all")
+
+export const cancelDownload = (id: string, keepPartial = false) =>
+  invoke<DownloadInfo>("cancel_download", { id, keepPartial })
 
 export const restartDownload = (id: string) => invoke<DownloadInfo>("restart_download", { id })
 
-export const removeDownload = (id: string) => invoke<void>("remove_download", { id })
+export const restartAllFailed = () => invoke<DownloadInfo[]>("restart_all_failed")
+
+export const duplicateDownload = (id: string) => invoke<DownloadInfo>("duplicate_download", { id })
+
+export const removeDownload = (id: string, deleteFile = false, trash = false) =>
+  invoke<void>("remove_download", { id, deleteFile, trash })
+
+export const listTrash = () => invoke<DownloadInfo[]>("list_trash")
+
+export const restoreDownload = (id: string) => invoke<DownloadInfo>("restore_download", { id })
+
+export const emptyTrash = () => invoke<number>("empty_trash")
+
+export const setPriority = (id: string, priority: number) =>
+  invoke<DownloadInfo>("set_priority", { id, priority })
+
+export const setTags = (id: string, tags: string[]) =>
+  invoke<DownloadInfo>("set_tags", { id, tags })
+
+export const renameDownload = (id: string, newName: string) =>
+  invoke<DownloadInfo>("rename_download", { id, newName })
+
+export const moveDownload = (id: string, newDirectory: string) =>
+  invoke<DownloadInfo>("move_download", { id, newDirectory })
+
+export const revealDownload = (id: string) => invoke<void>("reveal_download", { id })
+
+export const verifyDownload = (id: string, algorithm: HashAlgorithm, expected: string) =>
+  invoke<VerifyResult>("verify_download", { id, algorithm, expected })
+
+export const exportAsCurl = (id: string, revealCredentials = false) =>
+  invoke<string>("export_as_curl", { id, revealCredentials })
+
+export const updateExternalProgress = (
+  id: string,
+  downloadedBytes: number,
+  totalBytes: number | null,
+  speedBps: number,
+  status: DownloadStatus
+) =>
+  invoke<DownloadInfo>("update_external_progress", {
+    id,
+    downloadedBytes,
+    totalBytes,
+    speedBps,
+    status,
+  })
+
+export const clearCompleted = () => invoke<number>("clear_completed")
 
 export const setSpeedLimits = (limits: SpeedLimits) =>
   invoke<SpeedLimits>("set_speed_limits", { limits })
+
+export const setSchedule = (rules: ScheduleRule[]) =>
+  invoke<ScheduleRule[]>("set_schedule", { rules })
+
+export const setDownloadSpeedLimit = (id: string, bps?: number | null) =>
+  invoke<DownloadInfo>("set_download_speed_limit", { id, bps })
+
+export const getConcurrency = () => invoke<ConcurrencyInfo>("get_concurrency")
+
+export const setMaxConcurrent = (maxConcurrent: number) =>
+  invoke<ConcurrencyInfo>("set_max_concurrent", { maxConcurrent })
+
+export const setPerHostLimit = (limit: number | null) =>
+  invoke<DownloadSettings>("set_per_host_limit", { limit })
+
+export const setMaxSegmentConnections = (maxConnections: number) =>
+  invoke<DownloadSettings>("set_max_segment_connections", { maxConnections })
+
+export const setMaxRetries = (maxRetries: number) =>
+  invoke<DownloadSettings>("set_max_retries", { maxRetries })
+
+export const setMaxRedirects = (maxRedirects: number) =>
+  invoke<DownloadSettings>("set_max_redirects", { maxRedirects })
+
+export const setTimeouts = (connectTimeoutSecs: number, readTimeoutSecs: number) =>
+  invoke<DownloadSettings>("set_timeouts", { connectTimeoutSecs, readTimeoutSecs })
+
+export const setProxy = (url?: string | null) => invoke<DownloadSettings>("set_proxy", { url })
+
+export const setNoProxy = (patterns: string[]) =>
+  invoke<DownloadSettings>("set_no_proxy", { patterns })
+
+export const setUserAgent = (ua: string) => invoke<DownloadSettings>("set_user_agent", { ua })
+
+export const setCookies = (url: string, cookies: [string, string][], persist = false) =>
+  invoke<void>("set_cookies", { url, cookies, persist })
+
+export const setDiskSpaceCheck = (enabled: boolean) =>
+  invoke<DownloadSettings>("set_disk_space_check", { enabled })
+
+export const setAutoResume = (enabled: boolean) =>
+  invoke<DownloadSettings>("set_auto_resume", { enabled })
+
+export const setCategories = (categories: Record<string, string[]>) =>
+  invoke<DownloadSettings>("set_categories", { categories })
+
+export const setIpfsGateway = (url: string) =>
+  invoke<DownloadSettings>("set_ipfs_gateway", { url })
+
+export const setDefaultDirectory = (directory: string | null) =>
+  invoke<string | null>("set_default_directory", { directory })
+
+export const setOnCompleteCommand = (command: string | null) =>
+  invoke<DownloadSettings>("set_on_complete_command", { command })
+
+export const setNotifications = (onComplete: boolean, onFailure: boolean) =>
+  invoke<DownloadSettings>("set_notifications", { onComplete, onFailure })
+
+export const setMaxSize = (bytes: number | null) =>
+  invoke<DownloadSettings>("set_max_size", { bytes })
+
+export const setWriteBufferSize = (bytes: number) =>
+  invoke<DownloadSettings>("set_write_buffer_size", { bytes })
+
+export const setBufferedChunks = (chunks: number) =>
+  invoke<DownloadSettings>("set_buffered_chunks", { chunks })
+
+export const setSpeedSmoothing = (alpha: number) =>
+  invoke<DownloadSettings>("set_speed_smoothing", { alpha })
+
+export const setDedupeByChecksum = (enabled: boolean) =>
+  invoke<DownloadSettings>("set_dedupe_by_checksum", { enabled })
+
+export const setDecompression = (enabled: boolean) =>
+  invoke<DownloadSettings>("set_decompression", { enabled })
+
+export const setRestartIgnoredRange = (enabled: boolean) =>
+  invoke<DownloadSettings>("set_restart_ignored_range", { enabled })
+
+export const setTempDirectory = (directory: string | null) =>
+  invoke<DownloadSettings>("set_temp_directory", { directory })
+
+export const setDebugLogging = (enabled: boolean) =>
+  invoke<DownloadSettings>("set_debug_logging", { enabled })
+
+export const onDownloadProgress = (handler: (download: DownloadInfo) => void) =>
+  listen<DownloadInfo>("download:progress", (event) => handler(event.payload))
+
+export const onDownloadQueued = (handler: (download: DownloadInfo) => void) =>
+  listen<DownloadInfo>("download:queued", (event) => handler(event.payload))
+
+export const onDownloadStarted = (handler: (download: DownloadInfo) => void) =>
+  listen<DownloadInfo>("download:started", (event) => handler(event.payload))
+
+export const onDownloadFailed = (handler: (failure: DownloadFailure) => void) =>
+  listen<DownloadFailure>("download:failed", (event) => handler(event.payload))
+
+export const setProgressMode = (mode: ProgressMode, intervalMs?: number) =>
+  invoke<DownloadSettings>("set_progress_mode", { mode, intervalMs })
+
+export const onDownloadTick = (handler: (summaries: ProgressSummary[]) => void) =>
+  listen<ProgressSummary[]>("download:tick", (event) => handler(event.payload))
+
+export const onDownloadMetadata = (handler: (metadata: DownloadMetadata) => void) =>
+  listen<DownloadMetadata>("download:metadata", (event) => handler(event.payload))
+
+export const onDownloadHook = (handler: (result: HookResult) => void) =>
+  listen<HookResult>("download:hook", (event) => handler(event.payload))
+
+export const onDownloadLog = (handler: (entry: RequestLog) => void) =>
+  listen<RequestLog>("download:log", (event) => handler(event.payload))
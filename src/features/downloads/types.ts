@@ -1,19 +1,160 @@
 export type DownloadStatus =
   | "queued"
+  | "scheduled"
   | "running"
+  | "retrying"
   | "paused"
+  | "verifying"
   | "completed"
   | "failed"
   | "canceled"
   | "external"
 
-export type DownloadKind = "http" | "magnet" | "torrent"
+export type DownloadKind = "http" | "ftp" | "magnet" | "torrent" | "hls"
+
+export type DownloadSort = "createdAt" | "updatedAt" | "fileName" | "size"
+
+export type HashAlgorithm = "sha256" | "sha1" | "md5"
+
+export type VerifyResult = {
+  algorithm: HashAlgorithm
+  digest: string
+  matches: boolean
+}
+
+export type ConflictPolicy = "rename" | "overwrite" | "skip"
+
+export type AuthConfig =
+  | { type: "basic"; username: string; password: string }
+  | { type: "bearer"; token: string }
 
 export type SpeedLimits = {
   downloadBps?: number | null
   uploadBps?: number | null
 }
 
+export type ScheduleRule = {
+  start: string
+  end: string
+  downloadBps?: number | null
+}
+
+export type DownloadStats = {
+  activeCount: number
+  totalSpeedBps: number
+  sessionDownloadedBytes: number
+  byStatus: Partial<Record<DownloadStatus, number>>
+}
+
+export type SpeedSample = {
+  timestamp: number
+  speedBps: number
+}
+
+export type ProgressMode = "perDownload" | "tick"
+
+export type ProgressSummary = {
+  id: string
+  status: DownloadStatus
+  downloadedBytes: number
+  totalBytes: number | null
+  speedBps: number
+  etaSeconds: number | null
+}
+
+export type DownloadSettings = {
+  maxConcurrent: number
+  maxRetries: number
+  proxy?: string | null
+  noProxy: string[]
+  checkDiskSpace: boolean
+  maxRedirects: number
+  autoResume: boolean
+  connectTimeoutSecs: number
+  readTimeoutSecs: number
+  categories: Record<string, string[]>
+  writeBufferBytes: number
+  bufferedChunks: number
+  dedupeByChecksum: boolean
+  decompress: boolean
+  restartIgnoredRange: boolean
+  maxSegmentConnections: number
+  ipfsGateway: string
+  onCompleteCommand?: string | null
+  notifyOnComplete: boolean
+  notifyOnFailure: boolean
+  debugLogging: boolean
+  tempDirectory?: string | null
+  maxSizeBytes?: number | null
+  perHostLimit?: number | null
+  userAgent: string
+  progressMode: ProgressMode
+  tickIntervalMs: number
+  speedSmoothing: number
+}
+
+export type ConcurrencyInfo = {
+  maxConcurrent: number
+  active: number
+}
+
+export type UrlProbe = {
+  totalBytes: number | null
+  resumeSupported: boolean
+  fileName: string
+  contentType: string | null
+}
+
+export type BandwidthMeasurement = {
+  bytes: number
+  elapsedMs: number
+  speedBps: number
+  complete: boolean
+}
+
+export type DetectedUrl = {
+  url: string
+  kind: DownloadKind
+  fileName: string
+}
+
+export type DownloadFailure = {
+  id: string
+  error: string
+}
+
+export type HookResult = {
+  id: string
+  exitCode: number | null
+  success: boolean
+  error: string | null
+}
+
+export type CopyResult = {
+  path: string
+  success: boolean
+  error: string | null
+}
+
+export type DownloadMetadata = {
+  id: string
+  totalBytes: number | null
+  resumeSupported: boolean
+  fileName: string
+  contentType: string | null
+}
+
+export type RequestLog = {
+  id: string
+  timestamp: number
+  method: string
+  url: string
+  requestHeaders: [string, string][]
+  status: number | null
+  responseHeaders: [string, string][]
+  error: string | null
+}
+
 export type DownloadInfo = {
   id: string
   url: string
@@ -29,4 +170,84 @@ export type DownloadInfo = {
   updatedAt: number
   resumeSupported: boolean
   kind: DownloadKind
+  segmentCount?: number | null
+  expectedSha256?: string | null
+  sha256?: string | null
+  speedLimitBps?: number | null
+  etaSeconds?: number | null
+  etag?: string | null
+  lastModified?: string | null
+  mirrors?: string[]
+  activeUrl?: string | null
+  finalUrl?: string | null
+  preallocated?: boolean
+  scheduledAt?: number | null
+  averageSpeedBps?: number
+  rawSpeedBps?: number
+  contentType?: string | null
+  autoNamed?: boolean
+  httpStatus?: number | null
+  server?: string | null
+  infoHash?: string | null
+  trackerCount?: number
+  priority?: number
+  onConflict?: ConflictPolicy
+  retryCount?: number
+  inPlace?: boolean
+  maxSizeBytes?: number | null
+  extraDestinations?: string[]
+  copies?: CopyResult[]
+  ifModifiedSince?: number | null
+  notModified?: boolean
+  userAgent?: string | null
+  tags?: string[]
+  segments?: SegmentProgress[] | null
+  rangeStart?: number | null
+  rangeEnd?: number | null
+  playlistSegments?: number | null
+  playlistSegmentsDone?: number
+}
+
+export type SegmentProgress = {
+  start: number
+  end: number
+  downloaded: number
+}
+
+export type StartDownloadPayload = {
+  url: string
+  fileName?: string
+  directory?: string
+  kind?: DownloadKind
+  segments?: number
+  expectedSha256?: string
+  mirrors?: string[]
+  startAt?: number
+  priority?: number
+  onConflict?: ConflictPolicy
+  appendTo?: string
+  maxSizeBytes?: number
+  extraDestinations?: string[]
+  ifModifiedSince?: number
+  userAgent?: string
+  tags?: string[]
+  reuseInFlight?: boolean
+  rangeStart?: number
+  rangeEnd?: number
+  referer?: string
+  auth?: AuthConfig
+}
+
+export type ValidatedPayload = {
+  kind: DownloadKind
+  url: string
+  fileName: string
+  savePath: string
+  conflict: boolean
+  alreadyPresent: boolean
+}
+
+export type BatchStartResult = {
+  started: DownloadInfo[]
+  errors: { index: number; url: string; error: string }[]
 }
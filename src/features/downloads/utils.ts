@@ -38,8 +38,10 @@ export const sortDownloads = (downloads: DownloadInfo[]) =>
 
 export const inferKind = (input: string) => {
   const trimmed = input.trim().toLowerCase()
+  if (/^ftps?:\/\//.test(trimmed)) return "ftp" as const
   if (trimmed.startsWith("magnet:")) return "magnet" as const
   if (trimmed.endsWith(".torrent")) return "torrent" as const
+  if (/\.m3u8([?#]|$)/.test(trimmed)) return "hls" as const
   return "http" as const
 }
 
@@ -47,7 +49,7 @@ export const normalizeUrl = (input: string) => {
   const trimmed = input.trim()
   if (!trimmed) return ""
   if (trimmed.startsWith("magnet:")) return trimmed
-  if (/^https?:\/\//i.test(trimmed)) return trimmed
+  if (/^(https?|ftps?):\/\//i.test(trimmed)) return trimmed
   return `https://${trimmed}`
 }
 